@@ -1,5 +1,10 @@
+use std::collections::HashMap;
 use std::iter;
+use std::time::{Duration, Instant};
 
+use rand::Rng;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
 fn sln(x: f32) -> f32 { (x + 1.0).ln() }
 
@@ -57,6 +62,8 @@ const K_CAPITAL_DEPRECIATION: f32 = 0.9;
 const K_TIME_PREFERENCE: f32 = 0.9;
 
 const K_POLICY_DIFF_EPSILON: f32 = 0.00001;  // Actually diff^2
+const K_POLICY_LEARNING_RATE: f32 = 0.1;
+const K_POLICY_MAX_ITERS: usize = 200;
 
 /// $$
 /// p * \ln(k_tp * t_p + 1) * (1 + \ln(k_cp * c_e * C + 1))
@@ -83,70 +90,304 @@ fn find_best_land(map: &Map, agent_state: &AgentState) -> f32 {
         .unwrap()
 }
 
+fn calculate_agent_dV_dC(agent: &AgentNode, p: f32, next_dV_dC: f32) -> f32 {
+    // Cplus = C + p * \ln(k_tp * t_p + 1) * (1 + \ln(k_cp * C + 1))
+    // \pdv{Cplus}{C} =
+    //     1 + p * \ln(k_tp * t_p + 1) * \frac{k_cp}{k_cp * C + 1}
+    let dCplus_dC =
+        1.0
+        + p
+         * sln(K_TIME_PRODUCTIVITY * agent.action.t)
+         / (K_CAPITAL_PRODUCTIVITY * agent.state.capital + 1.0)
+    ;
+    // U = \ln(k_te * t_e + 1) * (1 + \ln(k_ce * c_e * Cplus + 1))
+    // \pdv{U}{Cplus} =
+    //     \ln(k_te * t_e + 1) * \frac{k_ce * c_e}{k_ce * c_e * Cplus + 1}
+    let c_e = 1.0 - agent.action.c;
+    let dU_dC =
+        sln(K_TIME_ENJOYMENT * (1.0 - agent.action.t))
+        * K_CAPITAL_ENJOYMENT * c_e
+        / (K_CAPITAL_ENJOYMENT * c_e * agent.capital_plus + 1.0)
+        * dCplus_dC
+    ;
+    let dCprime_dC = K_CAPITAL_DEPRECIATION * agent.action.c * dCplus_dC;
+    dU_dC + K_TIME_PREFERENCE * next_dV_dC * dCprime_dC
+}
+
+#[cfg(feature = "rayon")]
 fn calculate_dV_dC(node: &GameNode, next_dV_dCs: &Vec<f32>) -> Vec<f32> {
-    let mut dV_dC: Vec<f32> = vec![];
-    for (agent, next_dV_dC) in iter::zip(&node.agents, next_dV_dCs) {
-        let p = find_best_land(&node.map, &agent.state);
-        // Cplus = C + p * \ln(k_tp * t_p + 1) * (1 + \ln(k_cp * C + 1))
-        // \pdv{Cplus}{C} =
-        //     1 + p * \ln(k_tp * t_p + 1) * \frac{k_cp}{k_cp * C + 1}
-        let dCplus_dC =
-            1.0
-            + p
-             * sln(K_TIME_PRODUCTIVITY * agent.action.t)
-             / (K_CAPITAL_PRODUCTIVITY * agent.state.capital + 1.0)
-        ;
-        // U = \ln(k_te * t_e + 1) * (1 + \ln(k_ce * c_e * Cplus + 1))
-        // \pdv{U}{Cplus} =
-        //     \ln(k_te * t_e + 1) * \frac{k_ce * c_e}{k_ce * c_e * Cplus + 1}
-        let c_e = 1.0 - agent.action.c;
-        let dU_dC =
-            sln(K_TIME_ENJOYMENT * (1.0 - agent.action.t))
-            * K_CAPITAL_ENJOYMENT * c_e
-            / (K_CAPITAL_ENJOYMENT * c_e * agent.capital_plus + 1.0)
-            * dCplus_dC
-        ;
-        let dCprime_dC = K_CAPITAL_DEPRECIATION * agent.action.c * dCplus_dC;
-        dV_dC.push(dU_dC + K_TIME_PREFERENCE * next_dV_dC * dCprime_dC);
+    node.agents.par_iter().zip(next_dV_dCs.par_iter())
+        .map(|(agent, &next_dV_dC)| {
+            let p = find_best_land(&node.map, &agent.state);
+            calculate_agent_dV_dC(agent, p, next_dV_dC)
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "rayon"))]
+fn calculate_dV_dC(node: &GameNode, next_dV_dCs: &Vec<f32>) -> Vec<f32> {
+    iter::zip(&node.agents, next_dV_dCs)
+        .map(|(agent, &next_dV_dC)| {
+            let p = find_best_land(&node.map, &agent.state);
+            calculate_agent_dV_dC(agent, p, next_dV_dC)
+        })
+        .collect()
+}
+
+const K_ANNEALING_T0: f32 = 1.0;
+const K_ANNEALING_ITERS: usize = 200;
+const K_ANNEALING_COOLING: f32 = 0.98;
+const K_ANNEALING_CHAINS: usize = 4;
+
+/// Scores a land assignment by a cheap one-step forward rollout: each
+/// agent keeps its current action and capital, is given the land in
+/// `assignment`, and the resulting utilities are summed.
+fn evaluate_assignment(map: &Map, agents: &[AgentNode], assignment: &[usize]) -> f32 {
+    // `assignment` maps land index -> owner index; invert it into, per agent,
+    // the lands it owns under this assignment before scoring.
+    let mut owned_lands: Vec<Vec<usize>> = vec![Vec::new(); agents.len()];
+    for (land, &owner) in assignment.iter().enumerate() {
+        owned_lands[owner].push(land);
     }
-    return dV_dC;
+    iter::zip(agents, owned_lands)
+        .map(|(agent, lands)| {
+            let candidate_state = AgentState { lands, capital: agent.state.capital };
+            let p = find_best_land(map, &candidate_state);
+            let capital_plus =
+                agent.state.capital
+                + produce(p, agent.action.t, agent.action.c * agent.state.capital)
+            ;
+            consume(1.0 - agent.action.t, (1.0 - agent.action.c) * capital_plus)
+        })
+        .sum()
 }
 
-/// Re-evaluates a state assuming a new policy
+/// Runs one simulated-annealing chain over the assignment (land index ->
+/// owning agent), starting from `init_assignment`. Proposes a single land
+/// transfer per step, accepts a move that lowers the score by `d` with
+/// probability `exp(-d / T)`, and cools `T` geometrically from `K_ANNEALING_T0`
+/// over a fixed iteration budget. Returns the best-seen (assignment, score).
+fn run_annealing_chain(map: &Map, agents: &[AgentNode], init_assignment: &[usize]) -> (Vec<usize>, f32) {
+    let n = agents.len();
+    let mut assignment = init_assignment.to_vec();
+    let mut rng = rand::thread_rng();
+    let mut best = assignment.clone();
+    let mut best_score = evaluate_assignment(map, agents, &assignment);
+    let mut score = best_score;
+    let mut t = K_ANNEALING_T0;
+
+    for _ in 0..K_ANNEALING_ITERS {
+        let land = rng.gen_range(0..assignment.len());
+        let prev_owner = assignment[land];
+        let new_owner = rng.gen_range(0..n);
+        // Don't strip an agent of its last land; find_best_land requires at least one.
+        let prev_owner_land_count = assignment.iter().filter(|&&o| o == prev_owner).count();
+        if new_owner == prev_owner || prev_owner_land_count <= 1 {
+            continue;
+        }
+
+        assignment[land] = new_owner;
+        let candidate_score = evaluate_assignment(map, agents, &assignment);
+        let delta = candidate_score - score;
+
+        if delta >= 0.0 || rng.gen::<f32>() < (delta / t).exp() {
+            score = candidate_score;
+            if score > best_score {
+                best_score = score;
+                best = assignment.clone();
+            }
+        } else {
+            assignment[land] = prev_owner;
+        }
+        t *= K_ANNEALING_COOLING;
+    }
+
+    (best, best_score)
+}
+
+/// Reallocates land ownership by running `K_ANNEALING_CHAINS` independent
+/// simulated-annealing chains (in parallel, under the `rayon` feature) from
+/// the current ownership and keeping the best-scoring result. Lands that no
+/// agent holds start out assigned to agent 0.
+fn anneal_land_assignment(map: &Map, agents: &[AgentNode]) -> Vec<Vec<usize>> {
+    let n = agents.len();
+    let mut init_assignment: Vec<usize> = vec![0; map.lands.len()];
+    for (i, agent) in agents.iter().enumerate() {
+        for &land in &agent.state.lands {
+            init_assignment[land] = i;
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    let chains: Vec<(Vec<usize>, f32)> = (0..K_ANNEALING_CHAINS).into_par_iter()
+        .map(|_| run_annealing_chain(map, agents, &init_assignment))
+        .collect();
+    #[cfg(not(feature = "rayon"))]
+    let chains: Vec<(Vec<usize>, f32)> = (0..K_ANNEALING_CHAINS)
+        .map(|_| run_annealing_chain(map, agents, &init_assignment))
+        .collect();
+
+    let best = chains.into_iter()
+        .fold(None, |best: Option<(Vec<usize>, f32)>, cur| match best {
+            Some(b) if b.1 >= cur.1 => Some(b),
+            _ => Some(cur),
+        })
+        .unwrap().0;
+
+    let mut lands: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (land, &owner) in best.iter().enumerate() {
+        lands[owner].push(land);
+    }
+    lands
+}
+
+fn finalize_agent(map: &Map, agent: &mut AgentNode, result: &mut AgentState, assigned_lands: Vec<usize>) {
+    result.lands = assigned_lands;
+    let best_land = find_best_land(map, &agent.state);
+    agent.capital_plus =
+        agent.state.capital
+        + produce(best_land, agent.action.t, agent.action.c * agent.state.capital)
+    ;
+    agent.utility_yielded =
+        consume(1.0 - agent.action.t, (1.0 - agent.action.c) * agent.capital_plus)
+    ;
+    result.capital = agent.action.c * agent.capital_plus * K_CAPITAL_DEPRECIATION;
+}
+
+/// Re-evaluates a state assuming a new policy. Land ownership carries over
+/// unchanged; see `step_node_with_trading` for the variant that lets it
+/// shift via simulated annealing.
+#[cfg(feature = "rayon")]
+fn step_node(node: &mut GameNode, results: Vec<&mut AgentState>) {
+    node.agents.par_iter_mut().zip(results.into_par_iter())
+        .for_each(|(agent, result)| {
+            let lands = agent.state.lands.clone();
+            finalize_agent(node.map, agent, result, lands);
+        });
+}
+
+/// Re-evaluates a state assuming a new policy. Land ownership carries over
+/// unchanged; see `step_node_with_trading` for the variant that lets it
+/// shift via simulated annealing.
+#[cfg(not(feature = "rayon"))]
 fn step_node(node: &mut GameNode, results: Vec<&mut AgentState>) {
     for (agent, result) in iter::zip(&mut node.agents, results) {
-        // TODO: Implement trading lands
-        result.lands = agent.state.lands.clone();
-        let best_land = find_best_land(&node.map, &agent.state);
-        agent.capital_plus =
-            agent.state.capital
-            + produce(best_land, agent.action.t, agent.action.c * agent.state.capital)
+        let lands = agent.state.lands.clone();
+        finalize_agent(node.map, agent, result, lands);
+    }
+}
+
+/// Like `step_node`, but first reallocates land ownership across the whole
+/// map via `anneal_land_assignment` (multiple simulated-annealing chains,
+/// each with its own `rand::thread_rng()`). This is expensive and
+/// randomized, so it's a separate, explicit call rather than a hidden
+/// side effect of every `step_node`: callers that roll a node forward many
+/// times per search (`train_q_learning`, `evaluate_genome`) want the cheap
+/// deterministic `step_node`, while `solve`'s single game-sequence rollout
+/// opts into trading here.
+#[cfg(feature = "rayon")]
+fn step_node_with_trading(node: &mut GameNode, results: Vec<&mut AgentState>) {
+    let new_assignment = anneal_land_assignment(node.map, &node.agents);
+    node.agents.par_iter_mut().zip(results.into_par_iter()).enumerate()
+        .for_each(|(i, (agent, result))| {
+            finalize_agent(node.map, agent, result, new_assignment[i].clone());
+        });
+}
+
+/// See `step_node_with_trading` above.
+#[cfg(not(feature = "rayon"))]
+fn step_node_with_trading(node: &mut GameNode, results: Vec<&mut AgentState>) {
+    let new_assignment = anneal_land_assignment(node.map, &node.agents);
+    for (i, (agent, result)) in iter::zip(&mut node.agents, results).enumerate() {
+        finalize_agent(node.map, agent, result, new_assignment[i].clone());
+    }
+}
+
+/// Update node's policy given the next state's dV/dC's.
+///
+/// Each agent's (t, c) maximizes
+/// $$ V = consume(1-t, (1-c) Cplus) + k_{tpref} \cdot dV/dC_{next} \cdot Cprime $$
+/// where $Cplus = C + produce(p, t, cC)$ and $Cprime = k_{cdep} \cdot c \cdot Cplus$.
+/// We drive \pdv{V}{t} and \pdv{V}{c} to zero by projected gradient ascent
+/// on the box $t, c \in [0, 1]$: step along the gradient, clamp back into
+/// the box, and stop once the squared norm of the (unclamped) step is below
+/// `K_POLICY_DIFF_EPSILON`, or after `K_POLICY_MAX_ITERS` iterations.
+fn update_agent_policy(agent: &mut AgentNode, p: f32, dV_dC_next: f32) -> f32 {
+    let prev_action = agent.action.clone();
+    let capital = agent.state.capital;
+
+    let mut t = agent.action.t;
+    let mut c = agent.action.c;
+    for _ in 0..K_POLICY_MAX_ITERS {
+        let tt = K_TIME_PRODUCTIVITY * t;
+        let cp = K_CAPITAL_PRODUCTIVITY * c * capital;
+        // Cplus = C + p * sln(tt) * (1 + sln(cp))
+        let cplus = capital + p * sln(tt) * (1.0 + sln(cp));
+        // \pdv{Cplus}{t} = p * k_tp / (k_tp * t + 1) * (1 + sln(cp))
+        let dcplus_dt = p * K_TIME_PRODUCTIVITY / (tt + 1.0) * (1.0 + sln(cp));
+        // \pdv{Cplus}{c} = p * sln(tt) * k_cp * C / (k_cp * c * C + 1)
+        let dcplus_dc = p * sln(tt) * K_CAPITAL_PRODUCTIVITY * capital / (cp + 1.0);
+
+        let c_e = 1.0 - c;
+        let ce_cplus = K_CAPITAL_ENJOYMENT * c_e * cplus;
+        // \pdv{U}{t} =
+        //     -k_te / (k_te * (1-t) + 1) * (1 + sln(ce_cplus))
+        //     + sln(k_te * (1-t)) * k_ce * c_e * \pdv{Cplus}{t} / (ce_cplus + 1)
+        let du_dt =
+            -K_TIME_ENJOYMENT / (K_TIME_ENJOYMENT * (1.0 - t) + 1.0)
+            * (1.0 + sln(ce_cplus))
+            + sln(K_TIME_ENJOYMENT * (1.0 - t))
+            * K_CAPITAL_ENJOYMENT * c_e * dcplus_dt / (ce_cplus + 1.0)
         ;
-        agent.utility_yielded =
-            consume(1.0 - agent.action.t, (1.0 - agent.action.c) * agent.capital_plus)
+        // u = (1-c) * Cplus, \pdv{u}{c} = -Cplus + (1-c) * \pdv{Cplus}{c}
+        let du_dc = -cplus + c_e * dcplus_dc;
+        // \pdv{U}{c} = sln(k_te * (1-t)) * k_ce * \pdv{u}{c} / (ce_cplus + 1)
+        let du_dc_total =
+            sln(K_TIME_ENJOYMENT * (1.0 - t))
+            * K_CAPITAL_ENJOYMENT * du_dc / (ce_cplus + 1.0)
         ;
-        result.capital = agent.action.c * agent.capital_plus * K_CAPITAL_DEPRECIATION;
+
+        let dv_dt = du_dt
+            + K_TIME_PREFERENCE * dV_dC_next * K_CAPITAL_DEPRECIATION * c * dcplus_dt;
+        let dv_dc = du_dc_total
+            + K_TIME_PREFERENCE * dV_dC_next * K_CAPITAL_DEPRECIATION
+            * (cplus + c * dcplus_dc);
+
+        let step_t = K_POLICY_LEARNING_RATE * dv_dt;
+        let step_c = K_POLICY_LEARNING_RATE * dv_dc;
+        t = (t + step_t).clamp(0.0, 1.0);
+        c = (c + step_c).clamp(0.0, 1.0);
+
+        if step_t.powf(2.0) + step_c.powf(2.0) < K_POLICY_DIFF_EPSILON {
+            break;
+        }
     }
+
+    agent.action.t = t;
+    agent.action.c = c;
+    (agent.action.t - prev_action.t).powf(2.0) + (agent.action.c - prev_action.c).powf(2.0)
 }
 
-/// Update node's policy given the next state's dV/dC's
+#[cfg(feature = "rayon")]
 fn update_policy(node: &mut GameNode, dV_dCs: &Vec<f32>) -> Vec<f32> {
-    let mut diffs: Vec<f32> = Vec::new();
-    for (agent, _dV_dC) in node.agents.iter_mut().zip(dV_dCs) {
-        let prev_action = agent.action.clone();
-        // \pdv{V}{t} = 0 =
-        //     \pdv{U}{t} + k_tp * \pdv{Vprime}{Cprime} * \pdv{Cprime}{t}
-        // U = \ln(k_te * t + 1) * (1 + \ln(k_ce * C + 1))
-        // \pdv{U}{t} =
-        //     \frac{k_te}{k_te * t + 1} * (1 + \ln(c_ce))
-        agent.action.t = 0.0;  // TODO
-        agent.action.c = 0.0;  // TODO
-        diffs.push(
-            (agent.action.t - prev_action.c).powf(2.0)
-            + (agent.action.c - prev_action.c).powf(2.0)
-        );
-    }
-    return diffs;
+    let map = node.map;
+    node.agents.par_iter_mut().zip(dV_dCs.par_iter())
+        .map(|(agent, &dV_dC_next)| {
+            let p = find_best_land(map, &agent.state);
+            update_agent_policy(agent, p, dV_dC_next)
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "rayon"))]
+fn update_policy(node: &mut GameNode, dV_dCs: &Vec<f32>) -> Vec<f32> {
+    let map = node.map;
+    node.agents.iter_mut().zip(dV_dCs)
+        .map(|(agent, &dV_dC_next)| {
+            let p = find_best_land(map, &agent.state);
+            update_agent_policy(agent, p, dV_dC_next)
+        })
+        .collect()
 }
 
 /// Returns dV_dC for the root state.
@@ -161,7 +402,7 @@ fn solve(game_sequence: &mut [GameNode]) -> Vec<f32> {
         return calculate_dV_dC(root, &vec![0.0; root.agents.len()]);
     }
     loop {
-        step_node(root, tail[0].agents.iter_mut().map(|a| &mut a.state).collect());
+        step_node_with_trading(root, tail[0].agents.iter_mut().map(|a| &mut a.state).collect());
         let next_dV_dCs = solve(&mut tail[..]);
         let policy_diff = update_policy(root, &next_dV_dCs);
         if policy_diff.iter().all(|d| *d < K_POLICY_DIFF_EPSILON) {
@@ -170,6 +411,540 @@ fn solve(game_sequence: &mut [GameNode]) -> Vec<f32> {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Model-free alternative: tabular Q-learning
+//
+// Useful when `produce`/`consume` aren't differentiable, or the analytic
+// fixed point in `solve` fails to converge. `AgentState` and `Action` are
+// discretized onto a grid so a `QLearningAgent` can learn a policy purely
+// from the rewards (`utility_yielded`) a rolled-forward `GameNode` produces.
+// ---------------------------------------------------------------------------
+
+const K_PRODUCTIVITY_BUCKETS: usize = 10;
+const K_CAPITAL_BUCKETS: usize = 10;
+const K_BUCKET_WIDTH: f32 = 1.0;
+const K_ACTION_GRID: usize = 5;  // c and t are each discretized into this many steps
+
+type StateKey = (usize, usize);  // (best-land-productivity bucket, capital bucket)
+type ActionKey = (usize, usize);  // (c bucket, t bucket)
+
+fn discretize(x: f32, buckets: usize) -> usize {
+    ((x.max(0.0) / K_BUCKET_WIDTH) as usize).min(buckets - 1)
+}
+
+fn state_key(map: &Map, state: &AgentState) -> StateKey {
+    (discretize(find_best_land(map, state), K_PRODUCTIVITY_BUCKETS), discretize(state.capital, K_CAPITAL_BUCKETS))
+}
+
+fn action_grid() -> Vec<ActionKey> {
+    let mut grid = Vec::with_capacity(K_ACTION_GRID * K_ACTION_GRID);
+    for ci in 0..K_ACTION_GRID {
+        for ti in 0..K_ACTION_GRID {
+            grid.push((ci, ti));
+        }
+    }
+    grid
+}
+
+fn action_from_key(key: ActionKey) -> Action {
+    Action {
+        c: key.0 as f32 / (K_ACTION_GRID - 1) as f32,
+        t: key.1 as f32 / (K_ACTION_GRID - 1) as f32,
+    }
+}
+
+struct QLearningAgent {
+    learning_rate: f32,
+    exploration_prob: f32,
+    discount_rate: f32,
+    q: HashMap<StateKey, HashMap<ActionKey, f32>>,
+}
+
+impl QLearningAgent {
+    fn new(learning_rate: f32, exploration_prob: f32, discount_rate: f32) -> Self {
+        Self {
+            learning_rate,
+            exploration_prob,
+            discount_rate,
+            q: HashMap::new(),
+        }
+    }
+
+    fn value(&self, state: StateKey, action: ActionKey) -> f32 {
+        self.q.get(&state).and_then(|actions| actions.get(&action)).copied().unwrap_or(0.0)
+    }
+
+    /// Returns the best known (action, value) pair for `state`.
+    fn best_action(&self, state: StateKey) -> (ActionKey, f32) {
+        action_grid().into_iter()
+            .map(|action| (action, self.value(state, action)))
+            .fold(((0, 0), f32::NEG_INFINITY), |best, cur| if cur.1 > best.1 { cur } else { best })
+    }
+
+    fn choose_action(&self, state: StateKey) -> ActionKey {
+        let mut rng = rand::thread_rng();
+        if rng.gen::<f32>() < self.exploration_prob {
+            let grid = action_grid();
+            grid[rng.gen_range(0..grid.len())]
+        } else {
+            self.best_action(state).0
+        }
+    }
+
+    /// Q(s,a) <- Q(s,a) + lr * (r + discount * max_a' Q(s',a') - Q(s,a))
+    fn update(&mut self, state: StateKey, action: ActionKey, reward: f32, next_state: StateKey) {
+        let next_best = self.best_action(next_state).1;
+        let current = self.value(state, action);
+        let target = reward + self.discount_rate * next_best;
+        self.q.entry(state).or_default()
+            .insert(action, current + self.learning_rate * (target - current));
+    }
+}
+
+/// Trains one `QLearningAgent` per agent slot by rolling `init_node`
+/// forward `depth_goal` steps via `step_node`, choosing actions
+/// epsilon-greedily and updating toward the agent's realized
+/// `utility_yielded`.
+fn train_q_learning(map: &Map, init_node: &GameNode, depth_goal: usize, episodes: usize) -> Vec<QLearningAgent> {
+    let n = init_node.agents.len();
+    let mut learners: Vec<QLearningAgent> = (0..n)
+        .map(|_| QLearningAgent::new(0.1, 0.1, K_TIME_PREFERENCE))
+        .collect();
+
+    for _ in 0..episodes {
+        let mut node = init_node.clone();
+        for _ in 0..depth_goal {
+            let state_keys: Vec<StateKey> = node.agents.iter()
+                .map(|agent| state_key(map, &agent.state))
+                .collect();
+            let action_keys: Vec<ActionKey> = iter::zip(&state_keys, &learners)
+                .map(|(state, learner)| learner.choose_action(*state))
+                .collect();
+            for (agent, action) in node.agents.iter_mut().zip(&action_keys) {
+                agent.action = action_from_key(*action);
+            }
+
+            let mut next_node = GameNode::from(node.clone());
+            step_node(&mut node, next_node.agents.iter_mut().map(|a| &mut a.state).collect());
+
+            let next_state_keys: Vec<StateKey> = next_node.agents.iter()
+                .map(|agent| state_key(map, &agent.state))
+                .collect();
+            for (i, learner) in learners.iter_mut().enumerate() {
+                learner.update(state_keys[i], action_keys[i], node.agents[i].utility_yielded, next_state_keys[i]);
+            }
+            node = next_node;
+        }
+    }
+    learners
+}
+
+/// Writes each agent's greedy (argmax) action into every node of
+/// `game_sequence`, so a `GameNode`/`Map` solved by `train_q_learning` can
+/// be read the same way as one solved by `solve`.
+fn apply_greedy_policy(map: &Map, game_sequence: &mut [GameNode], learners: &[QLearningAgent]) {
+    for node in game_sequence.iter_mut() {
+        for (agent, learner) in node.agents.iter_mut().zip(learners) {
+            let key = state_key(map, &agent.state);
+            agent.action = action_from_key(learner.best_action(key).0);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Evolutionary alternative: genetic search over per-agent policies
+//
+// Useful when `produce`/`consume` describe a non-convex landscape the
+// gradient ascent in `update_policy` gets stuck on. A genome is the
+// sequence of every agent's `Action` across `game_sequence`; fitness is
+// the `K_TIME_PREFERENCE`-discounted sum of `utility_yielded` from
+// forward-simulating the whole sequence with `step_node`.
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+struct Genome {
+    actions: Vec<Vec<Action>>,  // actions[node_index][agent_index]
+}
+
+impl Genome {
+    fn random(game_length: usize, n_agents: usize) -> Self {
+        let mut rng = rand::thread_rng();
+        let actions = (0..game_length)
+            .map(|_| (0..n_agents)
+                .map(|_| Action { c: rng.gen::<f32>(), t: rng.gen::<f32>() })
+                .collect())
+            .collect();
+        Self { actions }
+    }
+
+    /// Perturbs a randomly chosen fraction of the genome's `c`/`t` values
+    /// by a uniform amount in `[-mutation_range, mutation_range]`, clamped
+    /// back into `[0, 1]`.
+    fn mutate(&self, mutation_rate: f32, mutation_range: f32) -> Self {
+        let mut rng = rand::thread_rng();
+        let actions = self.actions.iter()
+            .map(|node_actions| node_actions.iter()
+                .map(|action| {
+                    let mut action = action.clone();
+                    if rng.gen::<f32>() < mutation_rate {
+                        action.c = (action.c + rng.gen_range(-mutation_range..mutation_range)).clamp(0.0, 1.0);
+                    }
+                    if rng.gen::<f32>() < mutation_rate {
+                        action.t = (action.t + rng.gen_range(-mutation_range..mutation_range)).clamp(0.0, 1.0);
+                    }
+                    action
+                })
+                .collect())
+            .collect();
+        Self { actions }
+    }
+}
+
+/// Fitness-weighted blend of two parent genomes.
+fn breed(a: &Genome, b: &Genome, fitness_a: f32, fitness_b: f32) -> Genome {
+    let total = (fitness_a.max(0.0) + fitness_b.max(0.0)).max(f32::EPSILON);
+    let weight_a = fitness_a.max(0.0) / total;
+    let actions = iter::zip(&a.actions, &b.actions)
+        .map(|(node_a, node_b)| iter::zip(node_a, node_b)
+            .map(|(action_a, action_b)| Action {
+                c: weight_a * action_a.c + (1.0 - weight_a) * action_b.c,
+                t: weight_a * action_a.t + (1.0 - weight_a) * action_b.t,
+            })
+            .collect())
+        .collect();
+    Genome { actions }
+}
+
+/// Forward-simulates `game_sequence` under `genome`'s actions, summing
+/// `K_TIME_PREFERENCE`-discounted `utility_yielded` across nodes and agents.
+fn evaluate_genome(game_sequence: &[GameNode], genome: &Genome) -> f32 {
+    let mut nodes: Vec<GameNode> = game_sequence.to_vec();
+    let mut discount = 1.0;
+    let mut fitness = 0.0;
+    for i in 0..nodes.len() {
+        for (agent, action) in nodes[i].agents.iter_mut().zip(&genome.actions[i]) {
+            agent.action = action.clone();
+        }
+        if i + 1 < nodes.len() {
+            let (head, tail) = nodes.split_at_mut(i + 1);
+            step_node(&mut head[i], tail[0].agents.iter_mut().map(|a| &mut a.state).collect());
+        } else {
+            let mut sink: Vec<AgentState> = nodes[i].agents.iter().map(|a| a.state.clone()).collect();
+            step_node(&mut nodes[i], sink.iter_mut().collect());
+        }
+        fitness += discount * nodes[i].agents.iter().map(|a| a.utility_yielded).sum::<f32>();
+        discount *= K_TIME_PREFERENCE;
+    }
+    fitness
+}
+
+#[derive(Debug, Clone)]
+struct GeneticParameters {
+    population_size: usize,
+    generations: usize,
+    mutation_rate: f32,
+    mutation_range: f32,
+    elite_count: usize,
+}
+
+impl Default for GeneticParameters {
+    fn default() -> Self {
+        Self {
+            population_size: 64,
+            generations: 200,
+            mutation_rate: 0.1,
+            mutation_range: 0.1,
+            elite_count: 4,
+        }
+    }
+}
+
+/// Entry point mirroring `solve`: searches per-agent `(t, c)` policies
+/// across `game_sequence` by selection, fitness-weighted crossover, and
+/// mutation, writing the fittest genome's actions back into the nodes.
+/// Returns that genome's fitness.
+fn solve_genetic(game_sequence: &mut [GameNode], params: &GeneticParameters) -> f32 {
+    let game_length = game_sequence.len();
+    let n_agents = game_sequence[0].agents.len();
+    let mut rng = rand::thread_rng();
+
+    let mut population: Vec<Genome> = (0..params.population_size)
+        .map(|_| Genome::random(game_length, n_agents))
+        .collect();
+
+    let mut best_genome = population[0].clone();
+    let mut best_fitness = f32::NEG_INFINITY;
+
+    for _ in 0..params.generations {
+        // Each genome's fitness is independent of the others, so this
+        // map-reduce is the natural place to hand the population to a
+        // thread pool; the subsequent sort keeps ranking deterministic.
+        #[cfg(feature = "rayon")]
+        let mut scored: Vec<(f32, Genome)> = population.into_par_iter()
+            .map(|genome| {
+                let fitness = evaluate_genome(game_sequence, &genome);
+                (fitness, genome)
+            })
+            .collect();
+        #[cfg(not(feature = "rayon"))]
+        let mut scored: Vec<(f32, Genome)> = population.into_iter()
+            .map(|genome| {
+                let fitness = evaluate_genome(game_sequence, &genome);
+                (fitness, genome)
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+
+        if scored[0].0 > best_fitness {
+            best_fitness = scored[0].0;
+            best_genome = scored[0].1.clone();
+        }
+
+        let mut next_generation: Vec<Genome> = scored.iter()
+            .take(params.elite_count)
+            .map(|(_, genome)| genome.clone())
+            .collect();
+
+        while next_generation.len() < params.population_size {
+            let (fitness_a, parent_a) = &scored[rng.gen_range(0..scored.len())];
+            let (fitness_b, parent_b) = &scored[rng.gen_range(0..scored.len())];
+            let child = breed(parent_a, parent_b, *fitness_a, *fitness_b)
+                .mutate(params.mutation_rate, params.mutation_range);
+            next_generation.push(child);
+        }
+        population = next_generation;
+    }
+
+    for (node, actions) in game_sequence.iter_mut().zip(&best_genome.actions) {
+        for (agent, action) in node.agents.iter_mut().zip(actions) {
+            agent.action = action.clone();
+        }
+    }
+    best_fitness
+}
+
+// ---------------------------------------------------------------------------
+// Adversarial alternative: iterative-deepening minimax with alpha-beta pruning
+//
+// Models agents as strategic competitors over the map's limited high-`p`
+// lands, rather than as independent optimizers. Agents alternate moves;
+// each move is a (t, c) policy plus an optional land claim taken from
+// whichever agent currently holds it. Leaves are scored by a `ScoreConfig`
+// blend of own capital, utility, and advantage over the other agents.
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy)]
+struct ScoreConfig {
+    own_capital_weight: f32,
+    utility_weight: f32,
+    relative_advantage_weight: f32,
+}
+
+impl Default for ScoreConfig {
+    fn default() -> Self {
+        Self {
+            own_capital_weight: 1.0,
+            utility_weight: 1.0,
+            relative_advantage_weight: 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MinimaxState {
+    agents: Vec<AgentState>,
+}
+
+type MinimaxKey = (usize, usize, Vec<(usize, Vec<usize>)>);  // (moving agent, depth, per-agent (capital bucket, lands))
+
+fn minimax_key(state: &MinimaxState, moving_agent: usize, depth_remaining: usize) -> MinimaxKey {
+    let agents_key = state.agents.iter()
+        .map(|agent| (discretize(agent.capital, K_CAPITAL_BUCKETS), agent.lands.clone()))
+        .collect();
+    (moving_agent, depth_remaining, agents_key)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct MinimaxMove {
+    action: ActionKey,
+    claim_land: Option<usize>,
+}
+
+/// Every `(t, c)` on the action grid, each optionally paired with claiming
+/// a land `agent` doesn't already hold (taking it from its current owner).
+/// Never offers to strip an owner of its last land; `find_best_land`
+/// requires every agent to hold at least one.
+fn legal_moves(map: &Map, state: &MinimaxState, agent: usize) -> Vec<MinimaxMove> {
+    let mut moves = Vec::new();
+    for action in action_grid() {
+        moves.push(MinimaxMove { action, claim_land: None });
+        for land in 0..map.lands.len() {
+            if state.agents[agent].lands.contains(&land) {
+                continue;
+            }
+            let current_owner = state.agents.iter().position(|owner| owner.lands.contains(&land));
+            if current_owner.is_some_and(|owner| state.agents[owner].lands.len() <= 1) {
+                continue;
+            }
+            moves.push(MinimaxMove { action, claim_land: Some(land) });
+        }
+    }
+    moves
+}
+
+fn apply_move(map: &Map, state: &MinimaxState, agent: usize, mv: MinimaxMove) -> MinimaxState {
+    let mut next = state.clone();
+    if let Some(land) = mv.claim_land {
+        for owner in next.agents.iter_mut() {
+            owner.lands.retain(|&l| l != land);
+        }
+        next.agents[agent].lands.push(land);
+    }
+    let action = action_from_key(mv.action);
+    let p = find_best_land(map, &next.agents[agent]);
+    let capital = next.agents[agent].capital;
+    let capital_plus = capital + produce(p, action.t, action.c * capital);
+    next.agents[agent].capital = action.c * capital_plus * K_CAPITAL_DEPRECIATION;
+    next
+}
+
+/// Leaf evaluation: a weighted blend of the agent's own capital, the
+/// utility an even (t, c) = (0.5, 0.5) split of its best land would yield,
+/// and its capital advantage relative to the other agents.
+fn score_state(map: &Map, state: &MinimaxState, agent: usize, config: &ScoreConfig) -> f32 {
+    let own = &state.agents[agent];
+    let p = find_best_land(map, own);
+    let capital_plus = own.capital + produce(p, 0.5, 0.5 * own.capital);
+    let utility = consume(0.5, 0.5 * capital_plus);
+
+    let others: Vec<f32> = state.agents.iter().enumerate()
+        .filter(|(i, _)| *i != agent)
+        .map(|(_, a)| a.capital)
+        .collect();
+    let others_avg = if others.is_empty() { 0.0 } else { others.iter().sum::<f32>() / others.len() as f32 };
+
+    config.own_capital_weight * own.capital
+        + config.utility_weight * utility
+        + config.relative_advantage_weight * (own.capital - others_avg)
+}
+
+/// A cached `alpha_beta` result, tagged with what it actually proves about
+/// the true value: `Exact` (the search completed within its window),
+/// `Lower` (a beta cutoff — the true value is at least this), or `Upper`
+/// (an alpha cutoff — the true value is at most this). Only `Exact` can be
+/// reused unconditionally; `Lower`/`Upper` are only useful against a
+/// caller whose window they still bound.
+#[derive(Debug, Clone, Copy)]
+enum Bound {
+    Exact(f32),
+    Lower(f32),
+    Upper(f32),
+}
+
+fn alpha_beta(
+    map: &Map,
+    state: &MinimaxState,
+    moving_agent: usize,
+    root_agent: usize,
+    config: &ScoreConfig,
+    depth_remaining: usize,
+    mut alpha: f32,
+    mut beta: f32,
+    cache: &mut HashMap<MinimaxKey, Bound>,
+    deadline: Instant,
+) -> f32 {
+    if depth_remaining == 0 || Instant::now() >= deadline {
+        return score_state(map, state, root_agent, config);
+    }
+    let key = minimax_key(state, moving_agent, depth_remaining);
+    let original_alpha = alpha;
+    let original_beta = beta;
+    if let Some(&bound) = cache.get(&key) {
+        match bound {
+            Bound::Exact(v) => return v,
+            Bound::Lower(v) if v >= beta => return v,
+            Bound::Upper(v) if v <= alpha => return v,
+            Bound::Lower(v) => alpha = alpha.max(v),
+            Bound::Upper(v) => beta = beta.min(v),
+        }
+    }
+
+    let maximizing = moving_agent == root_agent;
+    let n = state.agents.len();
+    let mut value = if maximizing { f32::NEG_INFINITY } else { f32::INFINITY };
+
+    for mv in legal_moves(map, state, moving_agent) {
+        let next_state = apply_move(map, state, moving_agent, mv);
+        let score = alpha_beta(
+            map, &next_state, (moving_agent + 1) % n, root_agent, config,
+            depth_remaining - 1, alpha, beta, cache, deadline,
+        );
+        if maximizing {
+            value = value.max(score);
+            alpha = alpha.max(value);
+        } else {
+            value = value.min(score);
+            beta = beta.min(value);
+        }
+        if beta <= alpha || Instant::now() >= deadline {
+            break;
+        }
+    }
+
+    let bound = if value <= original_alpha {
+        Bound::Upper(value)
+    } else if value >= original_beta {
+        Bound::Lower(value)
+    } else {
+        Bound::Exact(value)
+    };
+    cache.insert(key, bound);
+    value
+}
+
+/// Iterative-deepening alpha-beta search over the joint land-acquisition
+/// and `(t, c)` action space, treating agents as adversarial competitors
+/// that alternate moves. Deepens until `time_budget` elapses, returning the
+/// best move found for `root_agent` at the deepest depth fully searched.
+fn minimax_search(
+    map: &Map,
+    root_state: &MinimaxState,
+    root_agent: usize,
+    config: &ScoreConfig,
+    time_budget: Duration,
+) -> MinimaxMove {
+    let n = root_state.agents.len();
+    let deadline = Instant::now() + time_budget;
+    let mut cache: HashMap<MinimaxKey, Bound> = HashMap::new();
+    let mut best_move = legal_moves(map, root_state, root_agent)[0];
+    let mut depth = 1;
+
+    while Instant::now() < deadline {
+        cache.clear();
+        let mut depth_best_move = best_move;
+        let mut depth_best_score = f32::NEG_INFINITY;
+        for mv in legal_moves(map, root_state, root_agent) {
+            if Instant::now() >= deadline {
+                break;
+            }
+            let next_state = apply_move(map, root_state, root_agent, mv);
+            let score = alpha_beta(
+                map, &next_state, (root_agent + 1) % n, root_agent, config,
+                depth - 1, f32::NEG_INFINITY, f32::INFINITY, &mut cache, deadline,
+            );
+            if score > depth_best_score {
+                depth_best_score = score;
+                depth_best_move = mv;
+            }
+        }
+        if Instant::now() < deadline {
+            best_move = depth_best_move;
+        }
+        depth += 1;
+    }
+    best_move
+}
+
 fn main() {
     let map = Map {
         lands: vec![
@@ -198,4 +973,27 @@ fn main() {
         println!("{i}:");
         println!("  {:?}", node);
     }
+
+    let learners = train_q_learning(&map, &game_sequence[0], depth_goal, 500);
+    let mut q_sequence = game_sequence.clone();
+    apply_greedy_policy(&map, &mut q_sequence, &learners);
+    println!("Q-learning policy:");
+    for (i, node) in q_sequence.iter().enumerate() {
+        println!("{i}:");
+        println!("  {:?}", node);
+    }
+
+    let mut genetic_sequence = game_sequence.clone();
+    let genetic_fitness = solve_genetic(&mut genetic_sequence, &GeneticParameters::default());
+    println!("Genetic policy (fitness {genetic_fitness}):");
+    for (i, node) in genetic_sequence.iter().enumerate() {
+        println!("{i}:");
+        println!("  {:?}", node);
+    }
+
+    let root_state = MinimaxState {
+        agents: game_sequence[0].agents.iter().map(|agent| agent.state.clone()).collect(),
+    };
+    let best_move = minimax_search(&map, &root_state, 0, &ScoreConfig::default(), Duration::from_millis(50));
+    println!("Minimax best move for agent 0: {:?}", best_move);
 }